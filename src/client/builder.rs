@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use reqwest::Proxy;
+
+use crate::client::oauth::TokenManager;
+use crate::client::response::FcmError;
+use crate::client::{Client, RetryPolicy, ServiceAccountKey};
+
+/// Builds a `Client` with custom transport and retry settings.
+///
+/// ```no_run
+/// # use fcm::{ClientBuilder, ServiceAccountKey};
+/// # fn load_key() -> ServiceAccountKey { unimplemented!() }
+/// let client = ClientBuilder::new(load_key())
+///     .timeout(std::time::Duration::from_secs(30))
+///     .build()?;
+/// # Ok::<(), fcm::FcmError>(())
+/// ```
+pub struct ClientBuilder {
+    service_account: ServiceAccountKey,
+    timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+    proxy: Option<Proxy>,
+    retry_policy: RetryPolicy,
+    http_client: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
+    /// Starts building a client that authenticates as the given Firebase
+    /// service account.
+    pub fn new(service_account: ServiceAccountKey) -> ClientBuilder {
+        ClientBuilder {
+            service_account,
+            timeout: None,
+            pool_max_idle_per_host: usize::MAX,
+            proxy: None,
+            retry_policy: RetryPolicy::default(),
+            http_client: None,
+        }
+    }
+
+    /// Sets the per-request timeout. Unset by default, matching `reqwest`'s
+    /// own default of no timeout.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Caps the number of idle connections kept open per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> ClientBuilder {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Routes requests through the given proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> ClientBuilder {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Overrides the retry/backoff policy used by `Client::send`.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> ClientBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Uses a pre-configured `reqwest::Client` instead of building one from
+    /// the other settings on this builder.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> ClientBuilder {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Builds the `Client`, returning an error if the underlying HTTP client
+    /// could not be constructed (e.g. an invalid proxy or TLS backend).
+    pub fn build(self) -> Result<Client, FcmError> {
+        let http_client = match self.http_client {
+            Some(http_client) => http_client,
+            None => {
+                let mut builder = reqwest::ClientBuilder::new().pool_max_idle_per_host(self.pool_max_idle_per_host);
+
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+
+                builder.build().map_err(|e| FcmError::ClientBuild(e.to_string()))?
+            }
+        };
+
+        Ok(Client {
+            http_client,
+            token_manager: TokenManager::new(self.service_account),
+            retry_policy: self.retry_policy,
+        })
+    }
+}
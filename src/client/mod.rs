@@ -1,14 +1,27 @@
+mod builder;
+mod oauth;
 pub(crate) mod response;
+mod retry;
+mod topics;
 
-use crate::client::response::{ErrorReason, FcmError, FcmResponse, RetryAfter};
+pub use builder::ClientBuilder;
+pub use oauth::ServiceAccountKey;
+pub use retry::RetryPolicy;
+pub use topics::{TopicManagementResponse, TopicManagementResult};
+
+use crate::client::oauth::TokenManager;
+use crate::client::response::{decode_api_error, ErrorReason, FcmError, FcmResponse, RetryAfter};
 use crate::{Message, MessageInternal};
 use reqwest::header::RETRY_AFTER;
 use reqwest::{Body, StatusCode};
 use serde::Serialize;
+use std::time::Instant;
 
 /// An async client for sending the notification payload.
 pub struct Client {
     http_client: reqwest::Client,
+    token_manager: TokenManager,
+    retry_policy: RetryPolicy,
 }
 
 // will be used to wrap the message in a "message" field
@@ -25,20 +38,79 @@ impl MessageWrapper<'_> {
 }
 
 impl Client {
-    /// Get a new instance of Client.
-    pub fn new() -> Client {
-        let http_client = reqwest::ClientBuilder::new()
-            .pool_max_idle_per_host(usize::MAX)
+    /// Get a new instance of Client, authenticating as the given Firebase
+    /// service account, using the default transport and retry settings. The
+    /// access token needed to call the FCM v1 API is minted from the service
+    /// account's key and refreshed automatically as it nears expiry, so
+    /// callers never need to handle tokens themselves.
+    ///
+    /// Use `ClientBuilder` instead if you need to customize timeouts,
+    /// connection pooling, a proxy, or the retry policy.
+    pub fn new(service_account: ServiceAccountKey) -> Client {
+        ClientBuilder::new(service_account)
             .build()
-            .unwrap();
+            .expect("default client configuration should always build")
+    }
+
+    /// Starts building a `Client` with custom transport or retry settings.
+    pub fn builder(service_account: ServiceAccountKey) -> ClientBuilder {
+        ClientBuilder::new(service_account)
+    }
+
+    /// Sends the message, retrying on server errors and rate limiting
+    /// according to the client's `RetryPolicy`. A `401` forces a token
+    /// refresh and is retried once before `FcmError::Unauthorized` is
+    /// returned to the caller.
+    pub async fn send(&self, project_id: &str, message: Message) -> Result<FcmResponse, FcmError> {
+        self.with_retry(|| self.try_send(project_id, &message)).await
+    }
+
+    /// Runs `attempt_fn` under the client's `RetryPolicy`: a `401` forces a
+    /// token refresh and is retried once, and server errors/rate limiting
+    /// are retried with backoff (honoring `Retry-After`) until `max_attempts`
+    /// or `max_elapsed` is reached. Shared by `send` and the topic
+    /// management calls so every request gets the same resilience.
+    pub(crate) async fn with_retry<T, F, Fut>(&self, mut attempt_fn: F) -> Result<T, FcmError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, FcmError>>,
+    {
+        let started_at = Instant::now();
+        let mut attempt = 1;
+        let mut reauthenticated = false;
+
+        loop {
+            match attempt_fn().await {
+                Err(FcmError::Unauthorized) if !reauthenticated => {
+                    reauthenticated = true;
+                    self.token_manager.invalidate().await;
+                }
+                Err(err @ FcmError::ServerError(_)) if attempt < self.retry_policy.max_attempts => {
+                    let retry_after = match &err {
+                        FcmError::ServerError(retry_after) => *retry_after,
+                        _ => None,
+                    };
 
-        Client { http_client }
+                    let delay = self.retry_policy.delay_for(attempt, retry_after);
+
+                    if started_at.elapsed() + delay > self.retry_policy.max_elapsed {
+                        return Err(err);
+                    }
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
     }
 
-    pub async fn send(&self, access_token: &str, project_id: &str, message: Message) -> Result<FcmResponse, FcmError> {
+    async fn try_send(&self, project_id: &str, message: &Message) -> Result<FcmResponse, FcmError> {
+        let access_token = self.token_manager.token(&self.http_client).await?;
+
         let fin = message.finalize();
         let wrapper = MessageWrapper::new(&fin);
-        let payload = serde_json::to_vec(&wrapper).unwrap();
+        let payload = serde_json::to_vec(&wrapper)?;
 
         // https://firebase.google.com/docs/reference/fcm/rest/v1/projects.messages/send
         let url = format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", project_id);
@@ -47,7 +119,7 @@ impl Client {
             .http_client
             .post(&url)
             .header("Content-Type", "application/json")
-            .header("Authorization", access_token)
+            .header("Authorization", format!("Bearer {access_token}"))
             .body(Body::from(payload))
             .build()?;
 
@@ -63,7 +135,14 @@ impl Client {
 
         match response_status {
             StatusCode::OK => {
-                let fcm_response: FcmResponse = response.json().await.unwrap();
+                let body = response.bytes().await.map_err(FcmError::ReadBody)?;
+
+                let fcm_response: FcmResponse = serde_json::from_slice(&body).map_err(|source| {
+                    FcmError::DeserializeResponse {
+                        source,
+                        body: String::from_utf8_lossy(&body).into_owned(),
+                    }
+                })?;
 
                 match fcm_response.error {
                     Some(ErrorReason::Unavailable) => Err(FcmError::ServerError(retry_after)),
@@ -72,12 +151,12 @@ impl Client {
                 }
             }
             StatusCode::UNAUTHORIZED => Err(FcmError::Unauthorized),
-            StatusCode::BAD_REQUEST => {
-                let body = response.text().await.unwrap();
-                Err(FcmError::InvalidMessage(format!("Bad Request ({body}")))
-            }
+            StatusCode::TOO_MANY_REQUESTS => Err(FcmError::ServerError(retry_after)),
             status if status.is_server_error() => Err(FcmError::ServerError(retry_after)),
-            _ => Err(FcmError::InvalidMessage("Unknown Error".to_string())),
+            status => {
+                let body = response.bytes().await.map_err(FcmError::ReadBody)?;
+                Err(decode_api_error(status.as_u16(), &body))
+            }
         }
     }
 }
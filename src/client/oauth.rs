@@ -0,0 +1,134 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::client::response::FcmError;
+
+const FIREBASE_MESSAGING_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const JWT_LIFETIME: Duration = Duration::from_secs(3600);
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// The contents of a Firebase service-account JSON key file, as downloaded
+/// from the Google Cloud console. Only the fields needed to mint OAuth2
+/// bearer tokens are kept.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    pub token_uri: String,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_at: SystemTime,
+}
+
+/// Mints and caches OAuth2 bearer tokens for the FCM v1 API from a Firebase
+/// service-account key, refreshing them shortly before they expire.
+pub(crate) struct TokenManager {
+    service_account: ServiceAccountKey,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl TokenManager {
+    pub(crate) fn new(service_account: ServiceAccountKey) -> TokenManager {
+        TokenManager {
+            service_account,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns a valid bearer token, minting a new one if there's none cached
+    /// or the cached one is about to expire.
+    pub(crate) async fn token(&self, http_client: &reqwest::Client) -> Result<String, FcmError> {
+        let mut cached = self.cached.lock().await;
+
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > SystemTime::now() + REFRESH_MARGIN {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let fresh = self.mint_token(http_client).await?;
+        let access_token = fresh.access_token.clone();
+        *cached = Some(fresh);
+
+        Ok(access_token)
+    }
+
+    /// Discards the cached token, forcing the next call to `token` to mint a
+    /// fresh one. Used after the server rejects a token with 401.
+    pub(crate) async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    async fn mint_token(&self, http_client: &reqwest::Client) -> Result<CachedToken, FcmError> {
+        let assertion = self.sign_assertion()?;
+
+        let params = [
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", assertion.as_str()),
+        ];
+
+        let response = http_client
+            .post(&self.service_account.token_uri)
+            .form(&params)
+            .send()
+            .await
+            .map_err(FcmError::Transport)?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(FcmError::Auth(format!("token exchange failed: {body}")));
+        }
+
+        let token_response: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| FcmError::Auth(format!("could not decode token response: {e}")))?;
+
+        Ok(CachedToken {
+            access_token: token_response.access_token,
+            expires_at: SystemTime::now() + Duration::from_secs(token_response.expires_in),
+        })
+    }
+
+    fn sign_assertion(&self) -> Result<String, FcmError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| FcmError::Auth(format!("system clock is before the epoch: {e}")))?
+            .as_secs();
+
+        let claims = Claims {
+            iss: &self.service_account.client_email,
+            scope: FIREBASE_MESSAGING_SCOPE,
+            aud: &self.service_account.token_uri,
+            iat: now,
+            exp: now + JWT_LIFETIME.as_secs(),
+        };
+
+        let key = EncodingKey::from_rsa_pem(self.service_account.private_key.as_bytes())
+            .map_err(|e| FcmError::Auth(format!("invalid private key: {e}")))?;
+
+        encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .map_err(|e| FcmError::Auth(format!("could not sign JWT assertion: {e}")))
+    }
+}
@@ -0,0 +1,286 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+/// The response returned by a successful call to `Client::send`.
+#[derive(Debug, Deserialize)]
+pub struct FcmResponse {
+    /// The identifier of the accepted message, present on success.
+    pub name: Option<String>,
+    pub(crate) error: Option<ErrorReason>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub(crate) enum ErrorReason {
+    #[serde(rename = "UNAVAILABLE")]
+    Unavailable,
+    #[serde(rename = "INTERNAL")]
+    InternalServerError,
+}
+
+/// The delay a server asked us to wait before retrying, taken from a
+/// `Retry-After` response header.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryAfter {
+    pub(crate) delay: Duration,
+}
+
+impl FromStr for RetryAfter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(seconds) = s.parse::<u64>() {
+            return Ok(RetryAfter {
+                delay: Duration::from_secs(seconds),
+            });
+        }
+
+        // The header may also carry an HTTP-date instead of a number of seconds.
+        if let Ok(at) = httpdate::parse_http_date(s) {
+            let delay = at.duration_since(std::time::SystemTime::now()).unwrap_or_default();
+            return Ok(RetryAfter { delay });
+        }
+
+        Err(())
+    }
+}
+
+/// Errors that can occur while talking to the FCM v1 API.
+#[derive(Debug, Error)]
+pub enum FcmError {
+    #[error("unauthorized: the access token was rejected")]
+    Unauthorized,
+
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+
+    #[error("server error, retry after: {0:?}")]
+    ServerError(Option<RetryAfter>),
+
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    #[error("authentication error: {0}")]
+    Auth(String),
+
+    #[error("could not build http client: {0}")]
+    ClientBuild(String),
+
+    #[error("could not serialize message: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("could not read response body: {0}")]
+    ReadBody(reqwest::Error),
+
+    #[error("could not deserialize response body: {source} (body: {body})")]
+    DeserializeResponse { source: serde_json::Error, body: String },
+
+    /// A structured error returned by the FCM v1 API, e.g. for an
+    /// unregistered token or a quota violation.
+    #[error("fcm api error ({http_status} {status}): {message}")]
+    ApiError {
+        http_status: u16,
+        status: String,
+        message: String,
+        error_code: Option<FcmErrorCode>,
+    },
+}
+
+/// The `errorCode` carried in the `details` of an FCM v1 error response, as
+/// documented at
+/// <https://firebase.google.com/docs/reference/fcm/rest/v1/ErrorCode>.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum FcmErrorCode {
+    #[serde(rename = "UNREGISTERED")]
+    Unregistered,
+    #[serde(rename = "INVALID_ARGUMENT")]
+    InvalidArgument,
+    #[serde(rename = "SENDER_ID_MISMATCH")]
+    SenderIdMismatch,
+    #[serde(rename = "QUOTA_EXCEEDED")]
+    QuotaExceeded,
+    #[serde(rename = "UNAVAILABLE")]
+    Unavailable,
+    #[serde(rename = "INTERNAL")]
+    Internal,
+    #[serde(rename = "THIRD_PARTY_AUTH_ERROR")]
+    ThirdPartyAuthError,
+    /// An `errorCode` this crate doesn't know about yet. Keeps an
+    /// unrecognized code from failing deserialization of the whole error
+    /// envelope.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FcmErrorEnvelope {
+    pub(crate) error: FcmErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FcmErrorBody {
+    // The body's self-reported status code is not trusted over the
+    // transport's actual HTTP status (see `decode_api_error`), but it's
+    // still part of the envelope we deserialize.
+    #[allow(dead_code)]
+    pub(crate) code: u16,
+    pub(crate) status: String,
+    pub(crate) message: String,
+    #[serde(default)]
+    pub(crate) details: Vec<FcmErrorDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FcmErrorDetail {
+    #[serde(rename = "errorCode")]
+    pub(crate) error_code: Option<FcmErrorCode>,
+}
+
+impl FcmErrorBody {
+    pub(crate) fn error_code(&self) -> Option<FcmErrorCode> {
+        self.details.iter().find_map(|d| d.error_code)
+    }
+}
+
+impl FcmError {
+    /// The decoded FCM `errorCode`, if this is a structured `ApiError`
+    /// response. Callers can match on `FcmErrorCode::Unregistered` to prune
+    /// dead registration tokens.
+    pub fn error_code(&self) -> Option<FcmErrorCode> {
+        match self {
+            FcmError::ApiError { error_code, .. } => *error_code,
+            _ => None,
+        }
+    }
+}
+
+/// Parses the FCM v1 error envelope (`{"error": {"code", "status",
+/// "message", "details": [...]}}`) out of a non-2xx response body.
+pub(crate) fn decode_api_error(http_status: u16, body: &[u8]) -> FcmError {
+    match serde_json::from_slice::<FcmErrorEnvelope>(body) {
+        Ok(envelope) => FcmError::ApiError {
+            http_status,
+            status: envelope.error.status.clone(),
+            error_code: envelope.error.error_code(),
+            message: envelope.error.message,
+        },
+        Err(_) => FcmError::InvalidMessage(format!(
+            "unexpected error response ({http_status}): {}",
+            String::from_utf8_lossy(body)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_retry_after_delay_seconds() {
+        let retry_after: RetryAfter = "120".parse().unwrap();
+        assert_eq!(retry_after.delay, Duration::from_secs(120));
+    }
+
+    #[test]
+    fn parses_retry_after_http_date() {
+        let at = std::time::SystemTime::now() + Duration::from_secs(60);
+        let header = httpdate::fmt_http_date(at);
+
+        let retry_after: RetryAfter = header.parse().unwrap();
+
+        // Formatting/parsing the date truncates sub-second precision, so
+        // allow a little slack either side of the expected delay.
+        assert!(retry_after.delay.as_secs() >= 58 && retry_after.delay.as_secs() <= 60);
+    }
+
+    #[test]
+    fn rejects_garbage_retry_after_values() {
+        assert!("not a valid retry-after value".parse::<RetryAfter>().is_err());
+    }
+
+    #[test]
+    fn decodes_structured_api_error_with_error_code() {
+        let body = br#"{
+            "error": {
+                "code": 404,
+                "status": "NOT_FOUND",
+                "message": "Requested entity was not found.",
+                "details": [{
+                    "@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                    "errorCode": "UNREGISTERED"
+                }]
+            }
+        }"#;
+
+        match decode_api_error(404, body) {
+            FcmError::ApiError {
+                http_status,
+                status,
+                error_code,
+                ..
+            } => {
+                assert_eq!(http_status, 404);
+                assert_eq!(status, "NOT_FOUND");
+                assert_eq!(error_code, Some(FcmErrorCode::Unregistered));
+            }
+            other => panic!("expected ApiError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn uses_the_real_http_status_over_the_bodys_self_reported_code() {
+        // The transport status (400) and the body's own `code` (500)
+        // disagree here; the transport status must win.
+        let body = br#"{"error": {"code": 500, "status": "INTERNAL", "message": "oops"}}"#;
+
+        match decode_api_error(400, body) {
+            FcmError::ApiError { http_status, .. } => assert_eq!(http_status, 400),
+            other => panic!("expected ApiError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_api_error_without_details() {
+        let body = br#"{"error": {"code": 500, "status": "INTERNAL", "message": "oops"}}"#;
+
+        match decode_api_error(500, body) {
+            FcmError::ApiError { error_code, .. } => assert_eq!(error_code, None),
+            other => panic!("expected ApiError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tolerates_an_error_code_it_does_not_recognize() {
+        let body = br#"{
+            "error": {
+                "code": 400,
+                "status": "INVALID_ARGUMENT",
+                "message": "bad request",
+                "details": [{"errorCode": "SOME_FUTURE_ERROR_CODE"}]
+            }
+        }"#;
+
+        match decode_api_error(400, body) {
+            FcmError::ApiError { error_code, .. } => assert_eq!(error_code, Some(FcmErrorCode::Unknown)),
+            other => panic!("expected ApiError, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_invalid_message_on_empty_body() {
+        match decode_api_error(503, b"") {
+            FcmError::InvalidMessage(_) => {}
+            other => panic!("expected InvalidMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_invalid_message_on_malformed_body() {
+        match decode_api_error(400, b"not json") {
+            FcmError::InvalidMessage(_) => {}
+            other => panic!("expected InvalidMessage, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::client::response::RetryAfter;
+
+/// Governs how `Client::send` retries failed requests.
+///
+/// The default policy makes up to 5 attempts on server errors and rate
+/// limiting, backing off up to 30 seconds between attempts (or longer if the server
+/// asks for it via `Retry-After`), and gives up once 2 minutes have passed
+/// since the first attempt.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    pub(crate) base_delay: Duration,
+    pub(crate) max_delay: Duration,
+    pub(crate) max_retry_after: Duration,
+    pub(crate) max_elapsed: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries server errors or rate limiting;
+    /// `Client::send` returns on the first such error. A `401` is still
+    /// retried once after a token refresh.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(0),
+            max_delay: Duration::from_millis(0),
+            max_retry_after: Duration::from_millis(0),
+            max_elapsed: Duration::from_millis(0),
+        }
+    }
+
+    /// Caps the number of attempts `send` will make on server errors and
+    /// rate limiting; the single retry after a `401` token refresh is not
+    /// counted. Chainable, so it composes with the other `with_*` setters.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Caps the total wall-clock time `send` will spend retrying, measured
+    /// from the first attempt. A retry whose delay would cross this budget
+    /// is skipped and the triggering error is returned instead. Chainable,
+    /// so it composes with the other `with_*` setters.
+    pub fn with_max_elapsed(mut self, max_elapsed: Duration) -> RetryPolicy {
+        self.max_elapsed = max_elapsed;
+        self
+    }
+
+    /// The delay to wait after the given failed attempt (1-indexed) before
+    /// the next one, honoring a
+    /// server-specified `Retry-After` when present and otherwise falling
+    /// back to exponential backoff with jitter.
+    pub(crate) fn delay_for(&self, attempt: u32, retry_after: Option<RetryAfter>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            // A server-mandated delay gets its own, much larger ceiling - it
+            // must not be clamped down to the backoff cap, or we'd retry
+            // sooner than the server asked for.
+            return retry_after.delay.min(self.max_retry_after);
+        }
+
+        let backoff = self.backoff_without_jitter(attempt);
+        let jitter_ms = rand::thread_rng().gen_range(0..=backoff.as_millis().max(1) as u64 / 2);
+
+        (backoff + Duration::from_millis(jitter_ms)).min(self.max_delay)
+    }
+
+    /// The exponential backoff for the given attempt (1-indexed), before
+    /// jitter or the `max_delay` cap are applied: `base_delay * 2^(attempt -
+    /// 1)`, with the exponent capped at 10.
+    fn backoff_without_jitter(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(10);
+        self.base_delay.saturating_mul(1u32 << exponent)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_retry_after: Duration::from_secs(300),
+            max_elapsed: Duration::from_secs(120),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_is_honored_beyond_the_backoff_cap() {
+        let policy = RetryPolicy::default();
+        let retry_after = RetryAfter {
+            delay: Duration::from_secs(120),
+        };
+
+        assert_eq!(policy.delay_for(1, Some(retry_after)), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn retry_after_is_clamped_to_its_own_ceiling_not_max_delay() {
+        let policy = RetryPolicy::default();
+        let retry_after = RetryAfter {
+            delay: Duration::from_secs(3600),
+        };
+
+        assert_eq!(policy.delay_for(1, Some(retry_after)), policy.max_retry_after);
+    }
+
+    #[test]
+    fn backoff_with_jitter_never_exceeds_max_delay() {
+        let policy = RetryPolicy::default();
+
+        for attempt in 1..=10 {
+            assert!(policy.delay_for(attempt, None) <= policy.max_delay);
+        }
+    }
+
+    #[test]
+    fn backoff_grows_with_attempt_number() {
+        let policy = RetryPolicy::default();
+
+        let first = policy.backoff_without_jitter(1);
+        let second = policy.backoff_without_jitter(2);
+        let third = policy.backoff_without_jitter(3);
+
+        assert!(first < second);
+        assert!(second < third);
+        assert_eq!(second, first * 2);
+        assert_eq!(third, first * 4);
+    }
+
+    #[test]
+    fn with_max_attempts_and_with_max_elapsed_compose() {
+        let policy = RetryPolicy::default()
+            .with_max_attempts(10)
+            .with_max_elapsed(Duration::from_secs(60));
+
+        assert_eq!(policy.max_attempts, 10);
+        assert_eq!(policy.max_elapsed, Duration::from_secs(60));
+    }
+}
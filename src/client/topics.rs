@@ -0,0 +1,136 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::client::response::{FcmError, RetryAfter};
+use crate::client::Client;
+
+const BATCH_ADD_URL: &str = "https://iid.googleapis.com/iid/v1:batchAdd";
+const BATCH_REMOVE_URL: &str = "https://iid.googleapis.com/iid/v1:batchRemove";
+
+#[derive(Serialize)]
+struct TopicManagementRequest<'a> {
+    to: String,
+    registration_tokens: &'a [&'a str],
+}
+
+impl<'a> TopicManagementRequest<'a> {
+    fn new(topic: &str, tokens: &'a [&'a str]) -> TopicManagementRequest<'a> {
+        TopicManagementRequest {
+            to: format!("/topics/{topic}"),
+            registration_tokens: tokens,
+        }
+    }
+}
+
+/// The outcome of subscribing or unsubscribing a single registration token.
+#[derive(Debug, Deserialize)]
+pub struct TopicManagementResult {
+    error: Option<String>,
+}
+
+impl TopicManagementResult {
+    /// Whether the token was subscribed/unsubscribed successfully.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The error message reported for this token, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// The response to a batch topic subscription/unsubscription call, with one
+/// result per registration token, in the order the tokens were given in.
+#[derive(Debug, Deserialize)]
+pub struct TopicManagementResponse {
+    results: Vec<TopicManagementResult>,
+}
+
+impl TopicManagementResponse {
+    /// The per-token results, in the order the tokens were given in.
+    pub fn results(&self) -> &[TopicManagementResult] {
+        &self.results
+    }
+}
+
+impl Client {
+    /// Subscribes up to 1000 registration tokens to the given topic.
+    pub async fn subscribe_to_topic(
+        &self,
+        topic: &str,
+        tokens: &[&str],
+    ) -> Result<TopicManagementResponse, FcmError> {
+        self.manage_topic(BATCH_ADD_URL, topic, tokens).await
+    }
+
+    /// Unsubscribes up to 1000 registration tokens from the given topic.
+    pub async fn unsubscribe_from_topic(
+        &self,
+        topic: &str,
+        tokens: &[&str],
+    ) -> Result<TopicManagementResponse, FcmError> {
+        self.manage_topic(BATCH_REMOVE_URL, topic, tokens).await
+    }
+
+    /// Retries the request under the client's `RetryPolicy`, the same way
+    /// `send` does: a `401` refreshes the token and retries once, and
+    /// `429`/5xx responses back off honoring `Retry-After`.
+    async fn manage_topic(
+        &self,
+        url: &str,
+        topic: &str,
+        tokens: &[&str],
+    ) -> Result<TopicManagementResponse, FcmError> {
+        self.with_retry(|| self.try_manage_topic(url, topic, tokens)).await
+    }
+
+    async fn try_manage_topic(
+        &self,
+        url: &str,
+        topic: &str,
+        tokens: &[&str],
+    ) -> Result<TopicManagementResponse, FcmError> {
+        let access_token = self.token_manager.token(&self.http_client).await?;
+        let body = TopicManagementRequest::new(topic, tokens);
+
+        let response = self
+            .http_client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {access_token}"))
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|ra| ra.to_str().ok())
+            .and_then(|ra| ra.parse::<RetryAfter>().ok());
+
+        match status {
+            StatusCode::UNAUTHORIZED => return Err(FcmError::Unauthorized),
+            StatusCode::TOO_MANY_REQUESTS => return Err(FcmError::ServerError(retry_after)),
+            status if status.is_server_error() => return Err(FcmError::ServerError(retry_after)),
+            _ => {}
+        }
+
+        let body = response.bytes().await.map_err(FcmError::ReadBody)?;
+
+        if !status.is_success() {
+            return Err(FcmError::InvalidMessage(format!(
+                "topic management request failed: {}",
+                String::from_utf8_lossy(&body)
+            )));
+        }
+
+        serde_json::from_slice(&body).map_err(|source| FcmError::DeserializeResponse {
+            source,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}